@@ -33,6 +33,10 @@ use sp_runtime::{RuntimeAppPublic, BoundToRuntimeAppPublic};
 use sp_staking::SessionIndex;
 use sp_std::vec::Vec;
 
+/// The context used to domain-separate proof-of-possession signatures produced by
+/// `generate_session_keys_with_proof` from any other signature a session key might produce.
+pub const SESSION_KEY_POP_CONTEXT: &[u8] = b"substrate-session-key-pop";
+
 sp_api::decl_runtime_apis! {
 	/// Session keys runtime api.
 	pub trait SessionKeys {
@@ -49,12 +53,65 @@ sp_api::decl_runtime_apis! {
 		///
 		/// Returns the list of public raw public keys + key type.
 		fn decode_session_keys(encoded: Vec<u8>) -> Option<Vec<(Vec<u8>, KeyTypeId)>>;
+
+		/// Generate a set of session keys like [`generate_session_keys`], additionally signing
+		/// each key's own public bytes, `KeyTypeId` and [`SESSION_KEY_POP_CONTEXT`] with that
+		/// key's secret.
+		///
+		/// Returns the concatenated SCALE encoded public keys together with the concatenated
+		/// proof-of-possession signatures, in the same order.
+		fn generate_session_keys_with_proof(seed: Option<Vec<u8>>) -> (Vec<u8>, Vec<u8>);
+
+		/// Verify a proof-of-possession previously returned by
+		/// [`generate_session_keys_with_proof`].
+		///
+		/// Re-derives the per-key messages via the same decoding path used in
+		/// [`decode_session_keys`]. Returns `false` on any length mismatch or verification
+		/// failure.
+		fn verify_session_keys_proof(encoded: Vec<u8>, proof: Vec<u8>) -> bool;
+
+		/// Describe the session key layout expected by this runtime.
+		///
+		/// Unlike [`decode_session_keys`], which can only interpret a blob that already
+		/// matches the runtime's current key set, this describes the expected key types,
+		/// their cryptographic schemes, public key lengths and their ordinal position within
+		/// the concatenated blob. Clients can use it to generate, validate and pretty-print
+		/// session keys generically across chains, and survive key-set additions without a
+		/// client upgrade.
+		fn session_keys_info() -> Vec<SessionKeyInfo>;
 	}
 }
 
 /// Number of validators in a given session.
 pub type ValidatorCount = u32;
 
+/// The cryptographic scheme used by a session key.
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq, RuntimeDebug)]
+pub enum CryptoScheme {
+	/// Schnorrkel/Ristretto x25519 ("sr25519").
+	Sr25519,
+	/// Edwards curve 25519.
+	Ed25519,
+	/// ECDSA over secp256k1.
+	Ecdsa,
+	/// BLS12-381.
+	Bls,
+}
+
+/// Describes a single key type within the concatenated session keys blob returned by
+/// `generate_session_keys`.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+pub struct SessionKeyInfo {
+	/// The key type of this session key.
+	pub key_type: KeyTypeId,
+	/// The cryptographic scheme used by this key.
+	pub scheme: CryptoScheme,
+	/// The expected length, in bytes, of this key's raw public key.
+	pub public_key_len: u32,
+	/// The position of this key within the concatenated SCALE encoded blob.
+	pub ordinal: u32,
+}
+
 /// Proof of membership of a specific key in a given session.
 #[derive(Encode, Decode, Clone, Eq, PartialEq, Default, RuntimeDebug)]
 pub struct MembershipProof {
@@ -137,6 +194,28 @@ pub trait ValidatorSetWithIdentification<AccountId>: ValidatorSet<AccountId> {
 	type IdentificationOf: Convert<Self::ValidatorId, Option<Self::Identification>>;
 }
 
+/// `ValidatorSetWithIdentification` with the ability to look up historical validator sets and
+/// build `MembershipProof`s against them.
+pub trait HistoricalValidatorSet<AccountId>: ValidatorSetWithIdentification<AccountId> {
+	/// Returns the validators of the given historical session, if its history is still
+	/// retained.
+	fn validators_at(session: SessionIndex) -> Option<Vec<Self::ValidatorId>>;
+
+	/// Build a `MembershipProof` attesting that `key` was a validator in `session`.
+	///
+	/// Returns `None` if `session`'s history is not retained, or `key` was not a validator in
+	/// it.
+	fn prove_membership(session: SessionIndex, key: &Self::ValidatorId) -> Option<MembershipProof>;
+
+	/// Verify that `proof` attests `key`'s membership in the session it claims, by checking
+	/// `proof.trie_nodes` against the implementation's stored root for `proof.session`.
+	///
+	/// There is deliberately no default: a default falling back to `validators_at` would defeat
+	/// the point of a succinct merkle proof by requiring the full historical validator list to
+	/// still be retained, and would never actually check the proof bytes it was handed.
+	fn check_membership_proof(key: &Self::ValidatorId, proof: &MembershipProof) -> bool;
+}
+
 /// A session handler for specific key type.
 pub trait OneSessionHandler<ValidatorId>: BoundToRuntimeAppPublic {
 	/// The key type expected.
@@ -168,7 +247,22 @@ pub trait OneSessionHandler<ValidatorId>: BoundToRuntimeAppPublic {
 	fn on_before_session_ending() {}
 
 	/// A validator got disabled. Act accordingly until a new session begins.
-	fn on_disabled(_validator_index: usize);
+	fn on_disabled(validator: &ValidatorId, index: usize, reason: DisabledReason);
+
+	/// A previously disabled validator got re-enabled within the same session. Act
+	/// accordingly, e.g. undo whatever `on_disabled` put in place for it.
+	fn on_reenabled(_validator: &ValidatorId, _index: usize) {}
+}
+
+/// The reason a validator was disabled, passed to `OneSessionHandler::on_disabled`.
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq, RuntimeDebug)]
+pub enum DisabledReason {
+	/// Disabled as the result of a reported offence.
+	Offence,
+	/// Disabled by a root/governance origin, independent of any reported offence.
+	GovernanceForced,
+	/// Disabled through some other, manual mechanism.
+	Manual,
 }
 
 /// Generate the initial session keys with the given seeds, at the given block and store them in
@@ -192,3 +286,111 @@ where
 
 	Ok(())
 }
+
+/// Describes when a node's session keys are due for rotation.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug)]
+pub struct SessionKeyRotationConfig {
+	/// The session at which the node's current keys were last (re)set on-chain, or `None` if
+	/// no keys have been set yet.
+	pub last_rotated_at: Option<SessionIndex>,
+	/// How many sessions may elapse before the keys must be rotated again.
+	pub rotation_interval: SessionIndex,
+}
+
+#[cfg(feature = "std")]
+impl SessionKeyRotationConfig {
+	/// Returns `true` if, given `current_index`, the keys are missing or due for rotation.
+	pub fn is_due(&self, current_index: SessionIndex) -> bool {
+		match self.last_rotated_at {
+			Some(last_rotated_at) =>
+				current_index.saturating_sub(last_rotated_at) >= self.rotation_interval,
+			None => true,
+		}
+	}
+}
+
+/// Check whether the node's on-chain session keys are missing or have been in place for at
+/// least `config.rotation_interval` sessions, and if so mint a fresh set into the keystore.
+///
+/// This is meant to be driven from an offchain worker or a service task, polling
+/// `ValidatorSet::current_index` for the current `SessionIndex`. It only mints the new keys
+/// and reports them back; it deliberately does not construct or submit the `setKeys`
+/// extrinsic itself. Callers should do so the same way any other offchain-signed transaction
+/// is submitted (e.g. a `Signer`/`SendSignedTransaction`-style callback), passing along the
+/// returned encoded blob and decoded `(KeyTypeId, raw_pubkey)` list.
+///
+/// Returns `None` if rotation is not yet due.
+///
+/// # Errors
+///
+/// Returns [`RotationError::Undecodable`] if `generate_session_keys` produced a blob that
+/// `decode_session_keys` could not parse, rather than silently reporting an empty key set.
+#[cfg(feature = "std")]
+pub fn rotate_session_keys_if_due<Block, T>(
+	client: std::sync::Arc<T>,
+	at: &BlockId<Block>,
+	current_index: SessionIndex,
+	config: &SessionKeyRotationConfig,
+	seed: Option<Vec<u8>>,
+) -> Result<Option<(Vec<u8>, Vec<(Vec<u8>, KeyTypeId)>)>, RotationError<sp_api::ApiErrorFor<T, Block>>>
+where
+	Block: BlockT,
+	T: ProvideRuntimeApi<Block>,
+	T::Api: SessionKeys<Block>,
+{
+	if !config.is_due(current_index) {
+		return Ok(None);
+	}
+
+	let runtime_api = client.runtime_api();
+	let encoded = runtime_api.generate_session_keys(at, seed)?;
+	let decoded = runtime_api.decode_session_keys(at, encoded.clone())?
+		.ok_or(RotationError::Undecodable)?;
+
+	Ok(Some((encoded, decoded)))
+}
+
+/// Error returned by [`rotate_session_keys_if_due`].
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub enum RotationError<E> {
+	/// The underlying runtime API call failed.
+	Api(E),
+	/// `generate_session_keys` returned a blob that `decode_session_keys` could not parse.
+	Undecodable,
+}
+
+#[cfg(feature = "std")]
+impl<E> From<E> for RotationError<E> {
+	fn from(e: E) -> Self {
+		RotationError::Api(e)
+	}
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+	use super::SessionKeyRotationConfig;
+
+	fn config(last_rotated_at: Option<u32>) -> SessionKeyRotationConfig {
+		SessionKeyRotationConfig { last_rotated_at, rotation_interval: 10 }
+	}
+
+	#[test]
+	fn missing_keys_are_always_due() {
+		assert!(config(None).is_due(0));
+		assert!(config(None).is_due(100));
+	}
+
+	#[test]
+	fn not_due_before_interval_elapses() {
+		assert!(!config(Some(5)).is_due(5));
+		assert!(!config(Some(5)).is_due(14));
+	}
+
+	#[test]
+	fn due_once_interval_elapses() {
+		assert!(config(Some(5)).is_due(15));
+		assert!(config(Some(5)).is_due(100));
+	}
+}